@@ -0,0 +1,515 @@
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use std::fs::{self, create_dir_all, File};
+use std::io::{copy, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tar::Archive as TarArchive;
+use tauri::AppHandle;
+use tauri::Emitter;
+use xz2::read::XzDecoder;
+use zip::read::ZipArchive;
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+/// Default ceiling on total uncompressed bytes an archive may contain, as a
+/// zip-bomb guard. Override via [`ArchiveLimits`].
+pub const DEFAULT_MAX_UNCOMPRESSED_BYTES: u64 = 20 * 1024 * 1024 * 1024;
+/// Default ceiling on the number of entries an archive may contain.
+pub const DEFAULT_MAX_ENTRY_COUNT: u64 = 200_000;
+
+/// Caps enforced by [`extract_archive`]'s pre-pass, to guard against zip
+/// bombs (absurd entry counts or uncompressed sizes).
+pub struct ArchiveLimits {
+    pub max_uncompressed_bytes: u64,
+    pub max_entry_count: u64,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_uncompressed_bytes: DEFAULT_MAX_UNCOMPRESSED_BYTES,
+            max_entry_count: DEFAULT_MAX_ENTRY_COUNT,
+        }
+    }
+}
+
+/// One entry of an archive, as reported by [`preview_archive`]. Mirrors the
+/// shape of a typical file-listing metadata struct (name/size/kind/perms) so
+/// the UI can render it the same way it renders a directory listing.
+#[derive(Serialize, Clone)]
+pub struct ArchiveEntryPreview {
+    pub name: String,
+    pub size: u64,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub unix_mode: Option<u32>,
+}
+
+/// Archive formats that [`extract_archive`] knows how to unpack.
+enum ArchiveKind {
+    Zip,
+    TarGz,
+    TarXz,
+}
+
+impl ArchiveKind {
+    /// Sniffs the archive format from its magic bytes, since the download's
+    /// temp filename doesn't necessarily reflect the real container format.
+    fn sniff(file_path: &Path) -> Result<Self, String> {
+        let mut header = [0u8; 6];
+        let mut file = File::open(file_path)
+            .map_err(|e| format!("Failed to open archive to detect its type: {}", e))?;
+        let read = file
+            .read(&mut header)
+            .map_err(|e| format!("Failed to read archive header: {}", e))?;
+        let header = &header[..read];
+
+        if header.starts_with(&[0x50, 0x4B]) {
+            Ok(ArchiveKind::Zip)
+        } else if header.starts_with(&[0x1F, 0x8B]) {
+            Ok(ArchiveKind::TarGz)
+        } else if header.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            Ok(ArchiveKind::TarXz)
+        } else {
+            Err(format!(
+                "Unrecognized archive format for {}",
+                file_path.to_string_lossy()
+            ))
+        }
+    }
+}
+
+/// Reports whether `file_path` looks like an archive [`extract_archive`]
+/// knows how to unpack, by sniffing its magic bytes. Used where a single
+/// archive must be picked out of a directory that may hold other files.
+pub fn is_known_archive(file_path: &Path) -> bool {
+    ArchiveKind::sniff(file_path).is_ok()
+}
+
+/// Lexically resolves `relative` against `base` (no filesystem access, since
+/// the target directory may not exist yet), rejecting absolute paths and any
+/// path that climbs above `base` via `..`. Returns `None` if it escapes.
+fn resolve_within(base: &Path, relative: &Path) -> Option<PathBuf> {
+    let mut result = base.to_path_buf();
+    let base_depth = base.components().count();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if result.components().count() <= base_depth {
+                    return None;
+                }
+                result.pop();
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(result)
+}
+
+/// Scans an archive without extracting it: returns metadata for every entry,
+/// rejecting (before any destructive filesystem change) entries that escape
+/// `target_dir`, absolute paths, and symlinks whose target would resolve
+/// outside `target_dir`.
+pub fn preview_archive(
+    file_path: &String,
+    target_dir: &String,
+) -> Result<Vec<ArchiveEntryPreview>, String> {
+    let target_path = Path::new(target_dir);
+    match ArchiveKind::sniff(Path::new(file_path))? {
+        ArchiveKind::Zip => preview_zip(file_path, target_path),
+        ArchiveKind::TarGz => preview_tar(target_path, || {
+            File::open(file_path)
+                .map(GzDecoder::new)
+                .map_err(|e| format!("Failed to open temp file: {}", e))
+        }),
+        ArchiveKind::TarXz => preview_tar(target_path, || {
+            File::open(file_path)
+                .map(XzDecoder::new)
+                .map_err(|e| format!("Failed to open temp file: {}", e))
+        }),
+    }
+}
+
+fn preview_zip(
+    file_path: &String,
+    target_path: &Path,
+) -> Result<Vec<ArchiveEntryPreview>, String> {
+    let file = File::open(file_path).map_err(|e| format!("Failed to open temp file: {}", e))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+
+    let mut entries = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read file from zip: {}", e))?;
+
+        let name = file.name().to_string();
+        let relative = file
+            .enclosed_name()
+            .ok_or_else(|| format!("Zip entry \"{}\" has an unsafe path", name))?;
+        let resolved = resolve_within(target_path, &relative).ok_or_else(|| {
+            format!("Zip entry \"{}\" resolves outside the target directory", name)
+        })?;
+
+        let unix_mode = file.unix_mode();
+        let is_symlink = unix_mode
+            .map(|mode| mode & 0o170000 == 0o120000)
+            .unwrap_or(false);
+        if is_symlink {
+            // A zip symlink stores its target as the entry's content.
+            let mut link_name = String::new();
+            file.read_to_string(&mut link_name)
+                .map_err(|e| format!("Failed to read link target for \"{}\": {}", name, e))?;
+            let link_base = resolved.parent().unwrap_or(target_path);
+            if resolve_within(link_base, Path::new(&link_name)).is_none() {
+                return Err(format!(
+                    "Symlink entry \"{}\" points outside the target directory",
+                    name
+                ));
+            }
+        }
+
+        entries.push(ArchiveEntryPreview {
+            name,
+            size: file.size(),
+            is_dir: file.is_dir(),
+            is_symlink,
+            unix_mode,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn preview_tar<R: Read, F: Fn() -> Result<R, String>>(
+    target_path: &Path,
+    open: F,
+) -> Result<Vec<ArchiveEntryPreview>, String> {
+    let mut archive = TarArchive::new(open()?);
+    let raw_entries = match archive.entries() {
+        Ok(e) => e,
+        Err(e) => return Err(format!("Failed to read tar archive: {}", e)),
+    };
+
+    let mut entries = Vec::new();
+    for entry in raw_entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry from tar archive: {}", e))?;
+
+        let relative = entry
+            .path()
+            .map_err(|e| format!("Failed to read entry path from tar archive: {}", e))?
+            .into_owned();
+        let name = relative.to_string_lossy().to_string();
+        let resolved = resolve_within(target_path, &relative)
+            .ok_or_else(|| format!("Tar entry \"{}\" resolves outside the target directory", name))?;
+
+        let header = entry.header();
+        if header.entry_type().is_symlink() || header.entry_type().is_hard_link() {
+            let link_name = entry
+                .link_name()
+                .map_err(|e| format!("Failed to read link target for \"{}\": {}", name, e))?
+                .ok_or_else(|| format!("Symlink entry \"{}\" has no target", name))?;
+            let link_base = resolved.parent().unwrap_or(target_path);
+            if resolve_within(link_base, &link_name).is_none() {
+                return Err(format!(
+                    "Symlink entry \"{}\" points outside the target directory",
+                    name
+                ));
+            }
+        }
+
+        entries.push(ArchiveEntryPreview {
+            name,
+            size: entry.size(),
+            is_dir: header.entry_type().is_dir(),
+            is_symlink: header.entry_type().is_symlink(),
+            unix_mode: header.mode().ok(),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Cheap pre-pass check against the archive's own metadata, so an obviously
+/// oversized archive is rejected before extraction even starts. This is only
+/// a fast path: the header-declared `size` of an entry is whatever the
+/// archive's author put there and a crafted bomb can understate it, so
+/// [`extract_zip`] and [`extract_tar`] additionally enforce `limits` against
+/// bytes actually written via [`LimitedWriter`].
+fn validate_limits(entries: &[ArchiveEntryPreview], limits: &ArchiveLimits) -> Result<(), String> {
+    if entries.len() as u64 > limits.max_entry_count {
+        return Err(format!(
+            "Archive has {} entries, exceeding the limit of {}",
+            entries.len(),
+            limits.max_entry_count
+        ));
+    }
+
+    let total_size: u64 = entries.iter().map(|e| e.size).sum();
+    if total_size > limits.max_uncompressed_bytes {
+        return Err(format!(
+            "Archive would extract to {} bytes, exceeding the limit of {}",
+            total_size, limits.max_uncompressed_bytes
+        ));
+    }
+
+    Ok(())
+}
+
+/// Wraps a writer and aborts once more than `limit` total bytes have been
+/// written through it, so [`extract_zip`]/[`extract_tar`] can enforce
+/// [`ArchiveLimits::max_uncompressed_bytes`] against bytes actually extracted
+/// rather than an archive's (spoofable) header-declared sizes.
+struct LimitedWriter<'a, W: Write> {
+    inner: W,
+    written: &'a mut u64,
+    limit: u64,
+}
+
+impl<'a, W: Write> Write for LimitedWriter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if *self.written + buf.len() as u64 > self.limit {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "Archive exceeds the configured uncompressed size limit of {} bytes",
+                    self.limit
+                ),
+            ));
+        }
+        let written = self.inner.write(buf)?;
+        *self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Extracts a zip, tar.gz, or tar.xz archive into `target_dir`, sniffing the
+/// format from the archive's magic bytes. Emits `unzip-progress` as it goes.
+///
+/// Before touching `target_dir`, validates every entry against zip-slip
+/// (paths/symlinks escaping `target_dir`, absolute paths) and against
+/// `limits`, so a malformed or malicious archive is rejected before the
+/// existing `target_dir` is destructively removed.
+pub async fn extract_archive(
+    app: &AppHandle,
+    file_path: &String,
+    target_dir: &String,
+    limits: &ArchiveLimits,
+) -> Result<(), String> {
+    let entries = preview_archive(file_path, target_dir)?;
+    validate_limits(&entries, limits)?;
+
+    let target_path = Path::new(target_dir);
+    if target_path.exists() {
+        if let Err(e) = fs::remove_dir_all(target_path) {
+            return Err(format!("Failed to remove target directory: {}", e));
+        }
+    }
+    if let Err(e) = create_dir_all(target_path) {
+        return Err(format!("Failed to create target directory: {}", e));
+    }
+
+    let total_entries = entries.len() as u64;
+    match ArchiveKind::sniff(Path::new(file_path))? {
+        ArchiveKind::Zip => extract_zip(app, file_path, target_path, limits),
+        ArchiveKind::TarGz => extract_tar_gz(app, file_path, target_path, total_entries, limits),
+        ArchiveKind::TarXz => extract_tar_xz(app, file_path, target_path, total_entries, limits),
+    }
+}
+
+fn extract_zip(
+    app: &AppHandle,
+    file_path: &String,
+    target_path: &Path,
+    limits: &ArchiveLimits,
+) -> Result<(), String> {
+    let file = match File::open(file_path) {
+        Ok(f) => f,
+        Err(e) => {
+            return Err(format!("Failed to open temp file: {}", e));
+        }
+    };
+
+    let mut archive = match ZipArchive::new(file) {
+        Ok(a) => a,
+        Err(e) => {
+            return Err(format!("Failed to read zip archive: {}", e));
+        }
+    };
+
+    let total_files = archive.len();
+    let mut extracted_bytes: u64 = 0;
+    for i in 0..total_files {
+        let mut file = match archive.by_index(i) {
+            Ok(f) => f,
+            Err(e) => {
+                return Err(format!("Failed to read file from zip: {}", e));
+            }
+        };
+
+        let outpath = match file.enclosed_name() {
+            Some(path) => target_path.join(path),
+            None => continue,
+        };
+
+        if file.name().ends_with('/') {
+            if let Err(e) = create_dir_all(&outpath) {
+                return Err(format!("Failed to create directory: {}", e));
+            }
+        } else {
+            if let Some(p) = outpath.parent() {
+                if !p.exists() {
+                    if let Err(e) = create_dir_all(p) {
+                        return Err(format!("Failed to create parent directory: {}", e));
+                    }
+                }
+            }
+
+            let mut outfile = match File::create(&outpath) {
+                Ok(f) => f,
+                Err(e) => {
+                    return Err(format!("Failed to create output file: {}", e));
+                }
+            };
+
+            let mut limited = LimitedWriter {
+                inner: &mut outfile,
+                written: &mut extracted_bytes,
+                limit: limits.max_uncompressed_bytes,
+            };
+            if let Err(e) = copy(&mut file, &mut limited) {
+                return Err(format!("Failed to copy file content: {}", e));
+            }
+
+            #[cfg(unix)]
+            if let Some(mode) = file.unix_mode() {
+                let _ = fs::set_permissions(&outpath, fs::Permissions::from_mode(mode));
+            }
+        }
+
+        let progress = i as f64 / total_files as f64 * 100.0;
+        app.emit("unzip-progress", progress).unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+fn extract_tar_gz(
+    app: &AppHandle,
+    file_path: &String,
+    target_path: &Path,
+    total_entries: u64,
+    limits: &ArchiveLimits,
+) -> Result<(), String> {
+    extract_tar(app, target_path, total_entries, limits, || {
+        File::open(file_path)
+            .map(GzDecoder::new)
+            .map_err(|e| format!("Failed to open temp file: {}", e))
+    })
+}
+
+fn extract_tar_xz(
+    app: &AppHandle,
+    file_path: &String,
+    target_path: &Path,
+    total_entries: u64,
+    limits: &ArchiveLimits,
+) -> Result<(), String> {
+    extract_tar(app, target_path, total_entries, limits, || {
+        File::open(file_path)
+            .map(XzDecoder::new)
+            .map_err(|e| format!("Failed to open temp file: {}", e))
+    })
+}
+
+/// Extracts a tar stream produced by `open`, emitting `unzip-progress` against
+/// `total_entries` (already known from `preview_archive`'s pre-pass) the same
+/// way the zip path does.
+///
+/// Regular files are copied by hand (rather than via `Entry::unpack`) through
+/// a [`LimitedWriter`] tracking bytes written across the whole archive, so
+/// `limits.max_uncompressed_bytes` is enforced against what's actually
+/// extracted instead of the entry's header-declared size.
+fn extract_tar<R: Read, F: Fn() -> Result<R, String>>(
+    app: &AppHandle,
+    target_path: &Path,
+    total_entries: u64,
+    limits: &ArchiveLimits,
+    open: F,
+) -> Result<(), String> {
+    let mut archive = TarArchive::new(open()?);
+    let entries = match archive.entries() {
+        Ok(e) => e,
+        Err(e) => return Err(format!("Failed to read tar archive: {}", e)),
+    };
+
+    let mut extracted_bytes: u64 = 0;
+    for (i, entry) in entries.enumerate() {
+        let mut entry = match entry {
+            Ok(e) => e,
+            Err(e) => return Err(format!("Failed to read entry from tar archive: {}", e)),
+        };
+
+        let entry_path = match entry.path() {
+            Ok(p) => p.into_owned(),
+            Err(e) => return Err(format!("Failed to read entry path from tar archive: {}", e)),
+        };
+        let outpath = target_path.join(entry_path);
+
+        if let Some(p) = outpath.parent() {
+            if !p.exists() {
+                if let Err(e) = create_dir_all(p) {
+                    return Err(format!("Failed to create parent directory: {}", e));
+                }
+            }
+        }
+
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            if let Err(e) = create_dir_all(&outpath) {
+                return Err(format!("Failed to create directory: {}", e));
+            }
+        } else if entry_type.is_symlink() || entry_type.is_hard_link() {
+            // No uncompressed bytes flow through a link entry, so it's
+            // exempt from the size limit; `Entry::unpack` also takes care of
+            // resolving/creating it for us.
+            if let Err(e) = entry.unpack(&outpath) {
+                return Err(format!("Failed to extract entry from tar archive: {}", e));
+            }
+        } else {
+            #[cfg(unix)]
+            let mode = entry.header().mode().ok();
+
+            let mut outfile = match File::create(&outpath) {
+                Ok(f) => f,
+                Err(e) => return Err(format!("Failed to create output file: {}", e)),
+            };
+            let mut limited = LimitedWriter {
+                inner: &mut outfile,
+                written: &mut extracted_bytes,
+                limit: limits.max_uncompressed_bytes,
+            };
+            if let Err(e) = copy(&mut entry, &mut limited) {
+                return Err(format!("Failed to extract entry from tar archive: {}", e));
+            }
+
+            #[cfg(unix)]
+            if let Some(mode) = mode {
+                let _ = fs::set_permissions(&outpath, fs::Permissions::from_mode(mode));
+            }
+        }
+
+        let progress = (i + 1) as f64 / total_entries.max(1) as f64 * 100.0;
+        app.emit("unzip-progress", progress).unwrap_or_default();
+    }
+
+    Ok(())
+}
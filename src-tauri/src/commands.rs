@@ -1,7 +1,10 @@
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use tauri::AppHandle;
 
-use crate::utils::{download_file, unzip_file};
+use crate::archive::{self, ArchiveEntryPreview, ArchiveLimits};
+use crate::github::{self, ArtifactSummary, PullRequestSummary, ResolvedReleaseAsset};
+use crate::repair;
+use crate::utils::{self, download_file, resolve_manifest_sha256};
 
 #[tauri::command]
 pub fn file_exists(path: String) -> bool {
@@ -13,12 +16,42 @@ pub async fn download_and_unzip(
     app: AppHandle,
     url: String,
     target_dir: String,
+    download_id: String,
+    expected_sha256: Option<String>,
+    checksum_manifest_url: Option<String>,
+    max_uncompressed_bytes: Option<u64>,
+    max_entry_count: Option<u64>,
 ) -> Result<(), String> {
     let temp_dir = std::env::temp_dir().to_string_lossy().to_string();
 
-    let temp_path = download_file(&app, &url, &temp_dir).await?;
+    let expected_sha256 = match checksum_manifest_url {
+        Some(manifest_url) => {
+            let filename = url
+                .rsplit('/')
+                .next()
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| format!("Could not determine archive filename from {}", url))?;
+            Some(resolve_manifest_sha256(&manifest_url, filename).await?)
+        }
+        None => expected_sha256,
+    };
+
+    let temp_path = download_file(
+        &app,
+        &url,
+        &temp_dir,
+        &download_id,
+        expected_sha256.as_deref(),
+        None,
+    )
+    .await?;
 
-    unzip_file(&app, &temp_path, &target_dir).await?;
+    let limits = ArchiveLimits {
+        max_uncompressed_bytes: max_uncompressed_bytes
+            .unwrap_or(archive::DEFAULT_MAX_UNCOMPRESSED_BYTES),
+        max_entry_count: max_entry_count.unwrap_or(archive::DEFAULT_MAX_ENTRY_COUNT),
+    };
+    archive::extract_archive(&app, &temp_path, &target_dir, &limits).await?;
 
     if let Err(e) = std::fs::remove_file(&temp_path) {
         return Err(format!("Failed to remove temp file: {}", e));
@@ -27,50 +60,80 @@ pub async fn download_and_unzip(
     Ok(())
 }
 
+/// Returns the entry list of an already-downloaded archive without
+/// extracting it, so the UI can show what `download_and_unzip` would install
+/// (and any overwrite conflicts) before committing to it.
 #[tauri::command]
-pub fn repair_server_runtime(target_dir: String) -> Result<Vec<String>, String> {
-    #[cfg(target_os = "windows")]
-    {
-        let target = PathBuf::from(target_dir);
-        if !target.exists() {
-            return Ok(vec![]);
-        }
+pub fn preview_archive(
+    file_path: String,
+    target_dir: String,
+) -> Result<Vec<ArchiveEntryPreview>, String> {
+    archive::preview_archive(&file_path, &target_dir)
+}
 
-        let system_root = std::env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".to_string());
-        let system32 = Path::new(&system_root).join("System32");
-        let runtime_dlls = [
-            "vcruntime140.dll",
-            "vcruntime140_1.dll",
-            "msvcp140.dll",
-            "msvcp140_1.dll",
-            "msvcp140_2.dll",
-            "vcomp140.dll",
-        ];
+/// Cancels an in-flight `download_and_unzip` call registered under
+/// `download_id`. A no-op if that download has already finished.
+#[tauri::command]
+pub fn cancel_download(download_id: String) {
+    utils::cancel_download(&download_id);
+}
 
-        let mut patched = Vec::new();
-        for dll in runtime_dlls {
-            let src = system32.join(dll);
-            let dst = target.join(dll);
-            if !src.exists() {
-                continue;
-            }
-            std::fs::copy(&src, &dst).map_err(|e| {
-                format!(
-                    "Failed to patch runtime dll {} -> {}: {}",
-                    src.to_string_lossy(),
-                    dst.to_string_lossy(),
-                    e
-                )
-            })?;
-            patched.push(dll.to_string());
-        }
+#[tauri::command]
+pub async fn resolve_github_release(
+    owner: String,
+    repo: String,
+    tag: Option<String>,
+    asset_pattern: String,
+) -> Result<ResolvedReleaseAsset, String> {
+    github::resolve_github_release(&owner, &repo, tag.as_deref(), &asset_pattern).await
+}
 
-        Ok(patched)
-    }
+#[tauri::command]
+pub async fn list_open_pull_requests(
+    owner: String,
+    repo: String,
+) -> Result<Vec<PullRequestSummary>, String> {
+    github::list_open_pull_requests(&owner, &repo).await
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = target_dir;
-        Ok(vec![])
-    }
+#[tauri::command]
+pub async fn list_run_artifacts(
+    owner: String,
+    repo: String,
+    head_sha: String,
+) -> Result<Vec<ArtifactSummary>, String> {
+    github::list_run_artifacts(&owner, &repo, &head_sha).await
+}
+
+#[tauri::command]
+pub async fn download_artifact(
+    app: AppHandle,
+    owner: String,
+    repo: String,
+    artifact_id: u64,
+    token: String,
+    target_dir: String,
+    download_id: String,
+    expected_sha256: Option<String>,
+) -> Result<(), String> {
+    github::download_artifact(
+        &app,
+        &owner,
+        &repo,
+        artifact_id,
+        &token,
+        &target_dir,
+        &download_id,
+        expected_sha256.as_deref(),
+    )
+    .await
+}
+
+/// Repairs native runtime dependencies under `target_dir` for the current
+/// platform (VC++ redistributables on Windows, `ldd`-driven `.so` patching on
+/// Linux, an `otool -L` report on macOS). Returns the libraries patched or
+/// reported missing.
+#[tauri::command]
+pub fn repair_server_runtime(target_dir: String) -> Result<Vec<String>, String> {
+    repair::repair_runtime(&target_dir)
 }
@@ -0,0 +1,312 @@
+use regex::Regex;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::archive::{extract_archive, is_known_archive, ArchiveLimits};
+use crate::utils::download_file;
+
+const USER_AGENT: &str = "MagicMirror";
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Serialize)]
+pub struct ResolvedReleaseAsset {
+    pub browser_download_url: String,
+    pub name: String,
+    pub size: u64,
+    pub checksum_asset_url: Option<String>,
+}
+
+/// Fetches a GitHub release (`latest`, or a specific `tag`) and picks the
+/// asset whose name matches `asset_pattern` (a regex). The literal tokens
+/// `{platform}` and `{arch}` in `asset_pattern` are substituted with the
+/// current platform/arch (via `tauri_plugin_os`) before matching, so callers
+/// can pass something like `myapp-{platform}-{arch}\.tar\.gz` and get the
+/// right asset for whatever machine MagicMirror is running on. If a sibling
+/// asset named `<match>.sha256` exists, its URL is returned too so the caller
+/// can feed it straight into the checksum-manifest mode of
+/// `download_and_unzip`.
+pub async fn resolve_github_release(
+    owner: &str,
+    repo: &str,
+    tag: Option<&str>,
+    asset_pattern: &str,
+) -> Result<ResolvedReleaseAsset, String> {
+    let url = match tag {
+        Some(tag) => format!(
+            "https://api.github.com/repos/{}/{}/releases/tags/{}",
+            owner, repo, tag
+        ),
+        None => format!(
+            "https://api.github.com/repos/{}/{}/releases/latest",
+            owner, repo
+        ),
+    };
+
+    let resolved_pattern = asset_pattern
+        .replace("{platform}", tauri_plugin_os::platform())
+        .replace("{arch}", tauri_plugin_os::arch());
+
+    let pattern = Regex::new(&resolved_pattern)
+        .map_err(|e| format!("Invalid asset pattern \"{}\": {}", resolved_pattern, e))?;
+
+    let client = Client::new();
+    let response = match client.get(&url).header("User-Agent", USER_AGENT).send().await {
+        Ok(res) => res,
+        Err(e) => return Err(format!("Failed to fetch release {}: {}", url, e)),
+    };
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "GitHub API returned {} for {}",
+            response.status(),
+            url
+        ));
+    }
+
+    let release = match response.json::<GithubRelease>().await {
+        Ok(r) => r,
+        Err(e) => return Err(format!("Failed to parse release response: {}", e)),
+    };
+
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| pattern.is_match(&a.name))
+        .ok_or_else(|| format!("No release asset matched pattern \"{}\"", asset_pattern))?;
+
+    let checksum_name = format!("{}.sha256", asset.name);
+    let checksum_asset_url = release
+        .assets
+        .iter()
+        .find(|a| a.name == checksum_name)
+        .map(|a| a.browser_download_url.clone());
+
+    Ok(ResolvedReleaseAsset {
+        browser_download_url: asset.browser_download_url.clone(),
+        name: asset.name.clone(),
+        size: asset.size,
+        checksum_asset_url,
+    })
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, String> {
+    let client = Client::new();
+    let response = match client.get(url).header("User-Agent", USER_AGENT).send().await {
+        Ok(res) => res,
+        Err(e) => return Err(format!("Failed to fetch {}: {}", url, e)),
+    };
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "GitHub API returned {} for {}",
+            response.status(),
+            url
+        ));
+    }
+
+    response
+        .json::<T>()
+        .await
+        .map_err(|e| format!("Failed to parse response from {}: {}", url, e))
+}
+
+#[derive(Deserialize)]
+struct PullRequestHead {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct PullRequestApi {
+    number: u64,
+    title: String,
+    head: PullRequestHead,
+}
+
+#[derive(Serialize)]
+pub struct PullRequestSummary {
+    pub number: u64,
+    pub title: String,
+    pub head_sha: String,
+}
+
+/// Lists a repo's open pull requests, so the UI can offer "install this PR's
+/// build" the way FlightCore lets users pick a Northstar PR build.
+pub async fn list_open_pull_requests(
+    owner: &str,
+    repo: &str,
+) -> Result<Vec<PullRequestSummary>, String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/pulls?state=open",
+        owner, repo
+    );
+    let pulls: Vec<PullRequestApi> = get_json(&url).await?;
+
+    Ok(pulls
+        .into_iter()
+        .map(|pr| PullRequestSummary {
+            number: pr.number,
+            title: pr.title,
+            head_sha: pr.head.sha,
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct WorkflowRun {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct WorkflowRunsResponse {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+#[derive(Deserialize)]
+struct Artifact {
+    id: u64,
+    name: String,
+    size_in_bytes: u64,
+}
+
+#[derive(Deserialize)]
+struct ArtifactsResponse {
+    artifacts: Vec<Artifact>,
+}
+
+#[derive(Serialize)]
+pub struct ArtifactSummary {
+    pub id: u64,
+    pub name: String,
+    pub size: u64,
+}
+
+/// Finds the artifacts produced by the most recent successful workflow run
+/// for `head_sha` (typically a PR's current head commit, from
+/// [`list_open_pull_requests`]).
+pub async fn list_run_artifacts(
+    owner: &str,
+    repo: &str,
+    head_sha: &str,
+) -> Result<Vec<ArtifactSummary>, String> {
+    let runs_url = format!(
+        "https://api.github.com/repos/{}/{}/actions/runs?head_sha={}&status=success",
+        owner, repo, head_sha
+    );
+    let runs: WorkflowRunsResponse = get_json(&runs_url).await?;
+    let run = runs
+        .workflow_runs
+        .first()
+        .ok_or_else(|| format!("No successful workflow run found for commit {}", head_sha))?;
+
+    let artifacts_url = format!(
+        "https://api.github.com/repos/{}/{}/actions/runs/{}/artifacts",
+        owner, repo, run.id
+    );
+    let artifacts: ArtifactsResponse = get_json(&artifacts_url).await?;
+
+    Ok(artifacts
+        .artifacts
+        .into_iter()
+        .map(|a| ArtifactSummary {
+            id: a.id,
+            name: a.name,
+            size: a.size_in_bytes,
+        })
+        .collect())
+}
+
+/// Downloads and installs a GitHub Actions artifact. Actions artifact
+/// downloads require auth (`token` should be a PAT or installation token with
+/// `actions:read`) and the download itself is a zip wrapping the artifact's
+/// real archive one level in, so this extracts twice: once to unwrap the
+/// Actions zip, then again into `target_dir`.
+pub async fn download_artifact(
+    app: &AppHandle,
+    owner: &str,
+    repo: &str,
+    artifact_id: u64,
+    token: &str,
+    target_dir: &str,
+    download_id: &str,
+    expected_sha256: Option<&str>,
+) -> Result<(), String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/actions/artifacts/{}/zip",
+        owner, repo, artifact_id
+    );
+    let temp_dir = std::env::temp_dir().to_string_lossy().to_string();
+
+    let outer_zip_path = download_file(
+        app,
+        &url,
+        &temp_dir,
+        download_id,
+        expected_sha256,
+        Some(&format!("Bearer {}", token)),
+    )
+    .await?;
+
+    let unwrap_dir = std::env::temp_dir().join(format!("magicmirror-artifact-{}", download_id));
+    let unwrap_dir_str = unwrap_dir.to_string_lossy().to_string();
+    extract_archive(
+        app,
+        &outer_zip_path,
+        &unwrap_dir_str,
+        &ArchiveLimits::default(),
+    )
+    .await?;
+
+    let inner_archive = find_inner_archive(&unwrap_dir)?;
+    let inner_archive_str = inner_archive.to_string_lossy().to_string();
+
+    extract_archive(
+        app,
+        &inner_archive_str,
+        &target_dir.to_string(),
+        &ArchiveLimits::default(),
+    )
+    .await?;
+
+    let _ = fs::remove_file(&outer_zip_path);
+    let _ = fs::remove_dir_all(&unwrap_dir);
+
+    Ok(())
+}
+
+/// Picks the artifact's real archive out of the directory the outer Actions
+/// zip unwrapped into. Rather than taking whatever file `read_dir` happens to
+/// list first (unspecified order, so this would otherwise be
+/// nondeterministic if the artifact has more than one top-level file), this
+/// looks for exactly one file that sniffs as a known archive format and
+/// errors instead of guessing when there's zero or more than one.
+fn find_inner_archive(unwrap_dir: &Path) -> Result<PathBuf, String> {
+    let candidates: Vec<PathBuf> = fs::read_dir(unwrap_dir)
+        .map_err(|e| format!("Failed to read unpacked artifact directory: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_known_archive(path))
+        .collect();
+
+    match candidates.len() {
+        0 => Err("Downloaded artifact did not contain a recognizable inner archive".to_string()),
+        1 => Ok(candidates.into_iter().next().unwrap()),
+        _ => Err(format!(
+            "Downloaded artifact contained {} candidate inner archives; expected exactly one",
+            candidates.len()
+        )),
+    }
+}
@@ -1,7 +1,13 @@
+mod archive;
 mod commands;
+mod github;
+mod repair;
 mod utils;
 
-use commands::{download_and_unzip, file_exists, repair_server_runtime};
+use commands::{
+    cancel_download, download_and_unzip, download_artifact, file_exists, list_open_pull_requests,
+    list_run_artifacts, preview_archive, repair_server_runtime, resolve_github_release,
+};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -12,7 +18,13 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             file_exists,
             download_and_unzip,
-            repair_server_runtime
+            repair_server_runtime,
+            resolve_github_release,
+            cancel_download,
+            list_open_pull_requests,
+            list_run_artifacts,
+            download_artifact,
+            preview_archive
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::Command;
+
+/// Repairs native runtime dependencies of the executables under
+/// `target_dir`, one platform-specific backend at a time: the original
+/// Windows VC++ redistributable copy, plus Linux `ldd`-driven `.so` patching
+/// and a macOS `otool -L` report. Returns the libraries patched or reported
+/// missing.
+pub fn repair_runtime(target_dir: &str) -> Result<Vec<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        repair_windows(target_dir)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        repair_linux(target_dir)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        repair_macos(target_dir)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    {
+        let _ = target_dir;
+        Ok(vec![])
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn repair_windows(target_dir: &str) -> Result<Vec<String>, String> {
+    let target = PathBuf::from(target_dir);
+    if !target.exists() {
+        return Ok(vec![]);
+    }
+
+    let system_root = std::env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".to_string());
+    let system32 = Path::new(&system_root).join("System32");
+    let runtime_dlls = [
+        "vcruntime140.dll",
+        "vcruntime140_1.dll",
+        "msvcp140.dll",
+        "msvcp140_1.dll",
+        "msvcp140_2.dll",
+        "vcomp140.dll",
+    ];
+
+    let mut patched = Vec::new();
+    for dll in runtime_dlls {
+        let src = system32.join(dll);
+        let dst = target.join(dll);
+        if !src.exists() {
+            continue;
+        }
+        std::fs::copy(&src, &dst).map_err(|e| {
+            format!(
+                "Failed to patch runtime dll {} -> {}: {}",
+                src.to_string_lossy(),
+                dst.to_string_lossy(),
+                e
+            )
+        })?;
+        patched.push(dll.to_string());
+    }
+
+    Ok(patched)
+}
+
+#[cfg(target_os = "linux")]
+const LIBRARY_SEARCH_PATHS: &[&str] = &[
+    "/usr/lib",
+    "/lib",
+    "/usr/lib64",
+    "/lib64",
+    "/usr/lib/x86_64-linux-gnu",
+    "/lib/x86_64-linux-gnu",
+    "/usr/lib/aarch64-linux-gnu",
+    "/lib/aarch64-linux-gnu",
+];
+
+#[cfg(target_os = "linux")]
+fn repair_linux(target_dir: &str) -> Result<Vec<String>, String> {
+    let target = Path::new(target_dir);
+    if !target.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut report = Vec::new();
+    for binary in find_native_binaries(target)? {
+        let output = match Command::new("ldd").arg(&binary).output() {
+            Ok(o) => o,
+            Err(_) => continue,
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        for line in stdout.lines() {
+            let line = line.trim();
+            if !line.ends_with("not found") {
+                continue;
+            }
+            let missing = match line.split_whitespace().next() {
+                Some(name) => name,
+                None => continue,
+            };
+
+            match find_system_library(missing) {
+                Some(src) => {
+                    let dst = target.join(missing);
+                    if std::fs::copy(&src, &dst).is_ok() {
+                        report.push(format!("{} (patched from {})", missing, src.to_string_lossy()));
+                    } else {
+                        report.push(format!("{} (found at {} but failed to copy)", missing, src.to_string_lossy()));
+                    }
+                }
+                None => {
+                    report.push(format!(
+                        "{} (missing; install the package that provides it)",
+                        missing
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(target_os = "linux")]
+fn find_system_library(name: &str) -> Option<PathBuf> {
+    LIBRARY_SEARCH_PATHS
+        .iter()
+        .map(|dir| Path::new(dir).join(name))
+        .find(|path| path.exists())
+}
+
+/// Path prefixes `repair_macos` never flags as missing: on macOS 11+ these
+/// live in the dyld shared cache, not as regular files, so `otool -L` lists
+/// them correctly but `Path::exists` on them is always false.
+#[cfg(target_os = "macos")]
+const SYSTEM_LIBRARY_PREFIXES: &[&str] = &["/usr/lib/", "/System/"];
+
+#[cfg(target_os = "macos")]
+fn repair_macos(target_dir: &str) -> Result<Vec<String>, String> {
+    let target = Path::new(target_dir);
+    if !target.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut report = Vec::new();
+    for binary in find_native_binaries(target)? {
+        let output = match Command::new("otool").arg("-L").arg(&binary).output() {
+            Ok(o) => o,
+            Err(_) => continue,
+        };
+        if !output.status.success() {
+            continue;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // The first line is the binary's own name, not a dependency.
+        for line in stdout.lines().skip(1) {
+            let dylib_path = match line.trim().split_whitespace().next() {
+                Some(p) => p,
+                None => continue,
+            };
+            if SYSTEM_LIBRARY_PREFIXES
+                .iter()
+                .any(|prefix| dylib_path.starts_with(prefix))
+            {
+                continue;
+            }
+            if !Path::new(dylib_path).exists() {
+                report.push(format!(
+                    "{} (missing; install the package that provides it)",
+                    dylib_path
+                ));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Walks `dir` looking for native binaries/libraries. Uses `entry.file_type()`
+/// (which, unlike `Path::is_dir`, does not follow symlinks) to skip symlinked
+/// directories rather than recurse into them — an archive staying within
+/// `target_dir` per the zip-slip checks in `archive.rs` can still contain a
+/// symlink back up to an ancestor directory, and following it here would
+/// recurse unbounded and blow the stack.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn find_native_binaries(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut binaries = Vec::new();
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.to_string_lossy(), e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read entry type: {}", e))?;
+        let path = entry.path();
+        if file_type.is_symlink() {
+            continue;
+        } else if file_type.is_dir() {
+            binaries.extend(find_native_binaries(&path)?);
+        } else if is_native_binary(&path) {
+            binaries.push(path);
+        }
+    }
+
+    Ok(binaries)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn is_native_binary(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    let is_shared_library = path
+        .file_name()
+        .map(|name| {
+            let name = name.to_string_lossy();
+            name.contains(".so") || name.ends_with(".dylib")
+        })
+        .unwrap_or(false);
+    if is_shared_library {
+        return true;
+    }
+
+    std::fs::metadata(path)
+        .map(|meta| meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
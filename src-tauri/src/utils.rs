@@ -1,12 +1,33 @@
 use futures_util::StreamExt;
-use reqwest::Client;
-use std::fs::{self, create_dir_all, File};
-use std::io::{copy, Write};
-use std::path::Path;
+use reqwest::{Client, StatusCode};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::AppHandle;
 use tauri::Emitter;
-use zip::read::ZipArchive;
+
+/// Error returned by [`download_file`] when [`cancel_download`] tripped its
+/// cancel flag mid-transfer.
+pub const CANCELLED: &str = "Cancelled";
+
+/// Cancel flags for in-flight downloads, keyed by the caller-supplied
+/// `download_id`. A download registers itself on start and deregisters on
+/// completion (success, failure, or cancellation).
+static CANCEL_FLAGS: LazyLock<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Requests cancellation of the download registered under `download_id`, if
+/// one is still in flight. A no-op if the download already finished.
+pub fn cancel_download(download_id: &str) {
+    if let Some(flag) = CANCEL_FLAGS.lock().unwrap().get(download_id) {
+        flag.store(true, Ordering::SeqCst);
+    }
+}
 
 pub fn timestamp() -> u128 {
     let start = SystemTime::now();
@@ -16,33 +37,221 @@ pub fn timestamp() -> u128 {
     duration.as_millis()
 }
 
+/// Path of the sidecar file recording the `ETag`/`Last-Modified` validator a
+/// partial download was started against, so a later resume can be checked
+/// for staleness before it's trusted. Lives next to the `.part` file.
+fn validator_path(temp_path: &Path) -> PathBuf {
+    let mut name = temp_path.as_os_str().to_os_string();
+    name.push(".validator");
+    PathBuf::from(name)
+}
+
+fn read_stored_validator(temp_path: &Path) -> Option<String> {
+    fs::read_to_string(validator_path(temp_path))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn store_validator(temp_path: &Path, validator: &str) {
+    let _ = fs::write(validator_path(temp_path), validator);
+}
+
+fn remove_validator(temp_path: &Path) {
+    let _ = fs::remove_file(validator_path(temp_path));
+}
+
+/// Extracts the validator a server response is identified by, preferring a
+/// strong `ETag` and falling back to `Last-Modified`, so it can be sent as
+/// `If-Range` on a later resume attempt.
+fn response_validator(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get(reqwest::header::ETAG)
+        .filter(|value| !value.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(reqwest::header::LAST_MODIFIED))
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+/// Hashes the bytes already on disk at `path`, returning the running
+/// [`Sha256`] so the caller can keep feeding it the bytes still to be
+/// downloaded (when resuming) or just finalize it as-is (when treating an
+/// existing partial as already complete).
+fn hasher_from_file(path: &Path) -> Result<Sha256, String> {
+    let mut hasher = Sha256::new();
+    let mut existing_file =
+        File::open(path).map_err(|e| format!("Failed to open partial file: {}", e))?;
+    let mut buf = [0u8; 8192];
+    loop {
+        let read = existing_file
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to hash partial file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher)
+}
+
+/// Downloads `url` into `temp_dir`, resuming a prior partial transfer for the
+/// same `download_id` via an HTTP `Range` request when one is found on disk
+/// together with the `ETag`/`Last-Modified` validator it was started against.
+/// Registers a cancel flag under `download_id` for the duration of the
+/// transfer; see [`cancel_download`].
 pub async fn download_file(
     app: &AppHandle,
     url: &String,
     temp_dir: &String,
+    download_id: &str,
+    expected_sha256: Option<&str>,
+    authorization: Option<&str>,
 ) -> Result<String, String> {
-    let temp_path = Path::new(temp_dir).join(format!("{}.zip", timestamp()));
+    let temp_path = Path::new(temp_dir).join(format!("{}.part", download_id));
+
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    CANCEL_FLAGS
+        .lock()
+        .unwrap()
+        .insert(download_id.to_string(), cancel_flag.clone());
+
+    let result = download_file_inner(
+        app,
+        url,
+        &temp_path,
+        &cancel_flag,
+        expected_sha256,
+        authorization,
+    )
+    .await;
+
+    CANCEL_FLAGS.lock().unwrap().remove(download_id);
+
+    result
+}
+
+async fn download_file_inner(
+    app: &AppHandle,
+    url: &String,
+    temp_path: &Path,
+    cancel_flag: &AtomicBool,
+    expected_sha256: Option<&str>,
+    authorization: Option<&str>,
+) -> Result<String, String> {
+    let existing = fs::metadata(temp_path).map(|m| m.len()).unwrap_or(0);
+    let stored_validator = read_stored_validator(temp_path);
+
+    // Only resume when we recorded a validator for this partial file. With
+    // none on hand there's no way to tell whether the remote content moved
+    // on since it was written (a moving `latest` URL, a re-cut release under
+    // the same download_id, ...), so discard the partial and start over
+    // rather than risk appending a new response onto stale bytes.
+    let can_resume = existing > 0 && stored_validator.is_some();
+    if existing > 0 && !can_resume {
+        let _ = fs::remove_file(temp_path);
+        remove_validator(temp_path);
+    }
 
     let client = Client::new();
-    let response = match client.get(url).send().await {
+    let mut request = client.get(url);
+    if let Some(authorization) = authorization {
+        request = request.header(reqwest::header::AUTHORIZATION, authorization);
+    }
+    if can_resume {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+        request = request.header(reqwest::header::IF_RANGE, stored_validator.unwrap());
+    }
+
+    let response = match request.send().await {
         Ok(res) => res,
         Err(e) => {
             return Err(format!("Failed to download file: {}", e));
         }
     };
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded = 0u64;
-    let mut file = match File::create(&temp_path) {
-        Ok(f) => f,
-        Err(e) => {
-            return Err(format!("Failed to create temp file: {}", e));
+    // The server can reject a Range request outright (most commonly 416,
+    // when the partial file already covers everything it has). Treat that
+    // as "the partial is already complete" instead of falling through to the
+    // fresh-download path below, which would otherwise truncate a good file
+    // and stream the error body into it.
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        if !can_resume {
+            return Err(format!(
+                "Server rejected range request for {} and no partial data exists",
+                url
+            ));
+        }
+        remove_validator(temp_path);
+        if let Some(expected) = expected_sha256 {
+            let digest = format!("{:x}", hasher_from_file(temp_path)?.finalize());
+            if !digest.eq_ignore_ascii_case(expected) {
+                let _ = fs::remove_file(temp_path);
+                return Err(format!(
+                    "Checksum mismatch: expected {}, got {}",
+                    expected.to_lowercase(),
+                    digest
+                ));
+            }
+        }
+        app.emit("download-progress", 100.0).unwrap_or_default();
+        return Ok(temp_path.to_string_lossy().to_string());
+    }
+
+    if response.status() != StatusCode::PARTIAL_CONTENT && response.status() != StatusCode::OK {
+        return Err(format!(
+            "Unexpected status {} downloading {}",
+            response.status(),
+            url
+        ));
+    }
+
+    // Only treat this as a resume if the server actually honored the
+    // conditional Range request; if it ignored Range/If-Range and sent the
+    // whole body back (status 200) we fall back to a fresh download, same as
+    // if there had been no usable partial file at all.
+    let resuming = can_resume && response.status() == StatusCode::PARTIAL_CONTENT;
+    if !resuming {
+        remove_validator(temp_path);
+        if let Some(validator) = response_validator(response.headers()) {
+            store_validator(temp_path, &validator);
+        }
+    }
+
+    let mut downloaded = if resuming { existing } else { 0 };
+    // `content_length()` is the size of *this response's* body, not the
+    // resumed total; on a 206 with no Content-Length it's `None`, and
+    // treating that as 0 would make `total_size` collapse to `downloaded`
+    // and progress read back 100%+ as soon as new bytes arrive. Keep the
+    // total unknown in that case instead of guessing.
+    let total_size = response.content_length().map(|len| downloaded + len);
+
+    let mut hasher = if resuming {
+        hasher_from_file(temp_path)?
+    } else {
+        Sha256::new()
+    };
+    let mut file = if resuming {
+        match OpenOptions::new().append(true).open(temp_path) {
+            Ok(f) => f,
+            Err(e) => return Err(format!("Failed to reopen partial file for append: {}", e)),
+        }
+    } else {
+        match File::create(temp_path) {
+            Ok(f) => f,
+            Err(e) => return Err(format!("Failed to create temp file: {}", e)),
         }
     };
 
     let mut stream = response.bytes_stream();
 
     while let Some(chunk) = stream.next().await {
+        if cancel_flag.load(Ordering::SeqCst) {
+            drop(file);
+            let _ = fs::remove_file(temp_path);
+            remove_validator(temp_path);
+            return Err(CANCELLED.to_string());
+        }
+
         let chunk = match chunk {
             Ok(c) => c,
             Err(e) => {
@@ -52,87 +261,69 @@ pub async fn download_file(
         if let Err(e) = file.write_all(&chunk) {
             return Err(format!("Failed to write chunk to file: {}", e));
         }
+        hasher.update(&chunk);
         downloaded += chunk.len() as u64;
-        if total_size > 0 {
+        if let Some(total_size) = total_size.filter(|&total| total > 0) {
             let progress = downloaded as f64 / total_size as f64 * 100.0;
             app.emit("download-progress", progress).unwrap_or_default();
         }
     }
 
+    if let Some(expected) = expected_sha256 {
+        let digest = format!("{:x}", hasher.finalize());
+        if !digest.eq_ignore_ascii_case(expected) {
+            let _ = fs::remove_file(temp_path);
+            remove_validator(temp_path);
+            return Err(format!(
+                "Checksum mismatch: expected {}, got {}",
+                expected.to_lowercase(),
+                digest
+            ));
+        }
+    }
+
+    remove_validator(temp_path);
     Ok(temp_path.to_string_lossy().to_string())
 }
 
-pub async fn unzip_file(
-    app: &AppHandle,
-    file_path: &String,
-    target_dir: &String,
-) -> Result<(), String> {
-    let file = match File::open(file_path) {
-        Ok(f) => f,
+/// Fetches a `.sha256`-style manifest (lines of `<hex digest>  <filename>`) and
+/// returns the digest for the entry whose filename matches `target_filename`.
+pub async fn resolve_manifest_sha256(
+    manifest_url: &str,
+    target_filename: &str,
+) -> Result<String, String> {
+    let client = Client::new();
+    let response = match client.get(manifest_url).send().await {
+        Ok(res) => res,
         Err(e) => {
-            return Err(format!("Failed to open temp file: {}", e));
+            return Err(format!("Failed to download checksum manifest: {}", e));
         }
     };
 
-    let target_path = Path::new(target_dir);
-    if target_path.exists() {
-        if let Err(e) = fs::remove_dir_all(target_path) {
-            return Err(format!("Failed to remove target directory: {}", e));
-        }
-    }
-    if let Err(e) = create_dir_all(target_path) {
-        return Err(format!("Failed to create target directory: {}", e));
-    }
-
-    let mut archive = match ZipArchive::new(file) {
-        Ok(a) => a,
+    let body = match response.text().await {
+        Ok(text) => text,
         Err(e) => {
-            return Err(format!("Failed to read zip archive: {}", e));
+            return Err(format!("Failed to read checksum manifest: {}", e));
         }
     };
 
-    let total_files = archive.len();
-    for i in 0..total_files {
-        let mut file = match archive.by_index(i) {
-            Ok(f) => f,
-            Err(e) => {
-                return Err(format!("Failed to read file from zip: {}", e));
-            }
+    for line in body.lines() {
+        let mut parts = line.split_whitespace();
+        let digest = match parts.next() {
+            Some(d) => d,
+            None => continue,
         };
-
-        let outpath = match file.enclosed_name() {
-            Some(path) => target_path.join(path),
+        let name = match parts.next() {
+            Some(n) => n,
             None => continue,
         };
-
-        if file.name().ends_with('/') {
-            if let Err(e) = create_dir_all(&outpath) {
-                return Err(format!("Failed to create directory: {}", e));
-            }
-        } else {
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    if let Err(e) = create_dir_all(p) {
-                        return Err(format!("Failed to create parent directory: {}", e));
-                    }
-                }
-            }
-
-            let mut outfile = match File::create(&outpath) {
-                Ok(f) => f,
-                Err(e) => {
-                    return Err(format!("Failed to create output file: {}", e));
-                }
-            };
-
-            if let Err(e) = copy(&mut file, &mut outfile) {
-                return Err(format!("Failed to copy file content: {}", e));
-            }
+        if name == target_filename || name.trim_start_matches('*') == target_filename {
+            return Ok(digest.to_string());
         }
-
-        let progress = i as f64 / total_files as f64 * 100.0;
-        app.emit("unzip-progress", progress).unwrap_or_default();
     }
 
-    Ok(())
+    Err(format!(
+        "No checksum entry for \"{}\" found in manifest {}",
+        target_filename, manifest_url
+    ))
 }